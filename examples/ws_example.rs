@@ -1,15 +1,23 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use connector_ftx::ws::{
-    EventHandler, FtxWsClient, WsChannel, WsDataEvent, WsResponseData
+    EventHandler, FtxWsClient, OrderBook, ReconnectPolicy, WsChannel, WsDataEvent, WsResponseData,
 };
 use rust_extensions::Logger;
 
-pub struct OrderBookHandler {}
+pub struct OrderBookHandler {
+    books: Mutex<HashMap<String, OrderBook>>,
+}
 
 impl OrderBookHandler {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            books: Mutex::new(HashMap::new()),
+        }
     }
 }
 
@@ -17,8 +25,24 @@ impl OrderBookHandler {
 impl EventHandler for OrderBookHandler {
     async fn on_data(&self, event: WsDataEvent) {
         if let WsResponseData::OrderbookData(orderbook_data) = event.data {
-            println!("Recieved orderbook {}:", event.market.unwrap());
-            println!("{:?}", orderbook_data);
+            let market = event.market.unwrap();
+            let mut books = self.books.lock().unwrap();
+            let book = books.entry(market.clone()).or_insert_with(OrderBook::new);
+
+            match book.apply(&orderbook_data) {
+                Ok(()) => {
+                    println!(
+                        "Recieved orderbook {}: best bid {:?}, best ask {:?}",
+                        market,
+                        book.best_bid(),
+                        book.best_ask()
+                    );
+                }
+                Err(err) => {
+                    println!("Orderbook {} failed to apply update: {}", market, err);
+                    books.remove(&market);
+                }
+            }
             println!("-------------------------------");
         }
     }
@@ -28,7 +52,7 @@ pub struct ConsoleLogger {}
 
 impl Logger for ConsoleLogger {
     fn write_info(&self, _process: String, _message: String, _ctx: Option<std::collections::HashMap<String, String>>) {
-        
+
     }
 
     fn write_warning(&self, _process: String, _message: String, _ctx: Option<std::collections::HashMap<String, String>>) {
@@ -52,11 +76,13 @@ async fn main() {
         WsChannel::Orderbook("BTC/USD".to_owned()),
         WsChannel::Orderbook("ETH/USD".to_owned()),
     ];
-    let event_handler = Arc::new(OrderBookHandler {});
+    let event_handler = Arc::new(OrderBookHandler::new());
     let ftx_ws = FtxWsClient::new(
         event_handler,
         Arc::new(ConsoleLogger{}),
         channels,
+        None,
+        ReconnectPolicy::default(),
     );
 
     FtxWsClient::start(Arc::new(ftx_ws));
@@ -1,4 +1,4 @@
-use crate::ws::WsChannel;
+use crate::ws::{WsChannel, WsMessageType};
 use thiserror::Error;
 use tokio_tungstenite::tungstenite;
 
@@ -10,6 +10,9 @@ pub enum WsError {
     #[error("Orderbook has not yet received partial")]
     MissingPartial,
 
+    #[error("Message of type {0:?} is missing its data payload")]
+    MissingData(WsMessageType),
+
     #[error("Not subscribed to this channel {0:?}")]
     NotSubscribedToThisChannel(WsChannel),
 
@@ -1,20 +1,73 @@
-use super::{models::*};
+use super::{models::*, WsError};
 
 #[async_trait::async_trait]
 pub trait EventHandler {
     async fn on_data(&self, event: WsDataEvent);
 }
 
+/// A decoded FTX message, carrying the channel and message type it arrived
+/// on alongside the typed payload, so a handler can tell a `Partial`
+/// snapshot from an `Update` delta without inspecting `data` itself.
 pub struct WsDataEvent {
     pub data: WsResponseData,
     pub market: Option<Symbol>,
+    pub channel: WsChannel,
+    pub message_type: WsMessageType,
 }
 
 impl WsDataEvent {
-    pub fn new(resp: WsResponse) -> Self {
-        Self {
-            data: resp.data.unwrap(),
+    pub fn new(resp: WsResponse) -> Result<Self, WsError> {
+        let channel = WsChannel::from_wire(resp.channel.as_deref().unwrap_or_default(), resp.market.as_deref());
+        let data = resp.data.ok_or(WsError::MissingData(resp.r#type))?;
+
+        Ok(Self {
+            message_type: resp.r#type,
             market: resp.market,
+            data,
+            channel,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(
+        channel: Option<&str>,
+        market: Option<&str>,
+        r#type: WsMessageType,
+        data: Option<WsResponseData>,
+    ) -> WsResponse {
+        WsResponse {
+            market: market.map(|s| s.to_string()),
+            channel: channel.map(|s| s.to_string()),
+            r#type,
+            data,
         }
     }
+
+    #[test]
+    fn missing_data_on_update_is_an_error() {
+        let resp = response(Some("ticker"), Some("BTC/USD"), WsMessageType::Update, None);
+
+        assert!(matches!(
+            WsDataEvent::new(resp),
+            Err(WsError::MissingData(WsMessageType::Update))
+        ));
+    }
+
+    #[test]
+    fn unrecognized_channel_surfaces_as_raw_instead_of_erroring() {
+        let resp = response(
+            Some("newFeature"),
+            Some("BTC/USD"),
+            WsMessageType::Update,
+            Some(WsResponseData::Raw(serde_json::json!({"foo": "bar"}))),
+        );
+
+        let event = WsDataEvent::new(resp).unwrap();
+
+        assert!(matches!(event.channel, WsChannel::Raw(_)));
+    }
 }
@@ -4,6 +4,7 @@ use futures::{
     Future, SinkExt, Stream, StreamExt,
 };
 use hmac_sha256::HMAC;
+use rust_decimal::prelude::ToPrimitive;
 use serde_json::json;
 use std::collections::VecDeque;
 use std::pin::Pin;
@@ -129,24 +130,52 @@ impl FtxWebsocket {
         };
 
         'channels: for channel in channels {
-            let (channel, symbol) = match channel {
-                WsChannel::Orderbook(symbol) => ("orderbook", symbol.as_str()),
-                WsChannel::Trades(symbol) => ("trades", symbol.as_str()),
-                WsChannel::Ticker(symbol) => ("ticker", symbol.as_str()),
-                WsChannel::Fills => ("fills", ""),
-                WsChannel::Orders => ("orders", ""),
+            let message = match channel {
+                WsChannel::Orderbook(symbol) => json!({
+                    "op": op,
+                    "channel": "orderbook",
+                    "market": symbol,
+                }),
+                WsChannel::Trades(symbol) => json!({
+                    "op": op,
+                    "channel": "trades",
+                    "market": symbol,
+                }),
+                WsChannel::Ticker(symbol) => json!({
+                    "op": op,
+                    "channel": "ticker",
+                    "market": symbol,
+                }),
+                WsChannel::Fills => json!({
+                    "op": op,
+                    "channel": "fills",
+                    "market": "",
+                }),
+                WsChannel::Orders => json!({
+                    "op": op,
+                    "channel": "orders",
+                    "market": "",
+                }),
+                WsChannel::OrderbookGrouped { symbol, grouping } => json!({
+                    "op": op,
+                    "channel": "orderbookGrouped",
+                    "market": symbol,
+                    "grouping": grouping.to_f64().unwrap_or_default(),
+                }),
+                // The stored value may have been built for the opposite
+                // direction (e.g. subscribed, then later unsubscribed) -
+                // override "op" rather than replaying it verbatim so
+                // unsubscribing a raw channel actually tells FTX to stop.
+                WsChannel::Raw(value) => {
+                    let mut message = value.clone();
+                    if let Some(object) = message.as_object_mut() {
+                        object.insert("op".to_string(), json!(op));
+                    }
+                    message
+                }
             };
 
-            self.stream
-                .send(Message::Text(
-                    json!({
-                        "op": op,
-                        "channel": channel,
-                        "market": symbol,
-                    })
-                    .to_string(),
-                ))
-                .await?;
+            self.stream.send(Message::Text(message.to_string())).await?;
 
             // Confirmation should arrive within the next 100 updates
             for _ in 0..100 {
@@ -220,6 +249,9 @@ impl FtxWebsocket {
                 WsResponseData::Order(order) => {
                     self.buf.push_back((response.market, EventData::Order(order)));
                 }
+                WsResponseData::Raw(value) => {
+                    self.buf.push_back((response.market, EventData::Raw(value)));
+                }
             }
         }
     }
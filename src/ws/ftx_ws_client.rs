@@ -1,25 +1,66 @@
 use my_web_socket_client::WebSocketClient;
 use my_web_socket_client::WsCallback;
 use my_web_socket_client::WsConnection;
+use rust_decimal::prelude::ToPrimitive;
 use rust_extensions::Logger;
 use serde_json::json;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
 
 use tokio_tungstenite::tungstenite::Message;
 
+use crate::ftx_auth_settings::FtxAuthSettings;
 use crate::ws::WsMessageType;
 
 use super::event_handler::*;
 use super::ftx_ws_settings::FtxWsSetting;
 use super::models::*;
 
+/// Controls how `FtxWsClient` re-establishes the connection once FTX drops
+/// it: how long to wait before the first retry, the cap the exponential
+/// backoff grows to, and how many consecutive attempts to make before giving
+/// up.
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// `None` keeps retrying forever.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// The delay to wait before reconnect attempt number `attempt` (0-based),
+    /// doubling from `initial_backoff` and capped at `max_backoff`.
+    fn backoff_for_attempt(&self, attempt: u32) -> Duration {
+        std::cmp::min(
+            self.initial_backoff * 2u32.saturating_pow(attempt.min(16)),
+            self.max_backoff,
+        )
+    }
+}
+
 pub struct FtxWsClient {
     event_handler: Arc<dyn EventHandler + Send + Sync + 'static>,
     ws_client: WebSocketClient,
     channels: Vec<WsChannel>,
+    auth: Option<FtxAuthSettings>,
     logger: Arc<dyn Logger + Send + Sync + 'static>,
+    reconnect_policy: ReconnectPolicy,
     is_started: AtomicBool,
+    keep_running: AtomicBool,
+    reconnect_attempts: AtomicU32,
+    self_ref: RwLock<Option<Weak<FtxWsClient>>>,
 }
 
 impl FtxWsClient {
@@ -27,17 +68,49 @@ impl FtxWsClient {
         event_handler: Arc<dyn EventHandler + Send + Sync + 'static>,
         logger: Arc<dyn Logger + Send + Sync + 'static>,
         channels: Vec<WsChannel>,
+        auth: Option<FtxAuthSettings>,
+        reconnect_policy: ReconnectPolicy,
     ) -> Self {
         let settings = Arc::new(FtxWsSetting::new());
         Self {
             event_handler,
             ws_client: WebSocketClient::new("FTX".to_string(), settings, logger.clone()),
             channels,
+            auth,
             logger,
+            reconnect_policy,
             is_started: AtomicBool::new(false),
+            keep_running: AtomicBool::new(false),
+            reconnect_attempts: AtomicU32::new(0),
+            self_ref: RwLock::new(None),
         }
     }
 
+    async fn login(&self, ws_connection: &Arc<WsConnection>) {
+        let auth = match &self.auth {
+            Some(auth) => auth,
+            None => return,
+        };
+
+        let timestamp = FtxAuthSettings::generate_timestamp();
+        let sign = auth.generate_sign("websocket_login", timestamp);
+
+        ws_connection
+            .send_message(Message::Text(
+                json!({
+                    "op": "login",
+                    "args": {
+                        "key": auth.api_key,
+                        "sign": sign,
+                        "time": timestamp as u64,
+                        "subaccount": auth.subaccount,
+                    }
+                })
+                .to_string(),
+            ))
+            .await;
+    }
+
     async fn subscribe_or_unsubscribe(
         &self,
         ws_connection: Arc<WsConnection>,
@@ -51,62 +124,163 @@ impl FtxWsClient {
         };
 
         for channel in channels {
-            let (channel, symbol) = match channel {
-                WsChannel::Orderbook(symbol) => ("orderbook", symbol),
-                WsChannel::Trades(symbol) => ("trades", symbol),
-                WsChannel::Ticker(symbol) => ("ticker", symbol),
-                WsChannel::Fills => ("fills", "".to_string()),
-                WsChannel::Orders => ("orders", "".to_string()),
+            let message = match channel {
+                WsChannel::Orderbook(symbol) => json!({
+                    "op": op,
+                    "channel": "orderbook",
+                    "market": symbol,
+                }),
+                WsChannel::Trades(symbol) => json!({
+                    "op": op,
+                    "channel": "trades",
+                    "market": symbol,
+                }),
+                WsChannel::Ticker(symbol) => json!({
+                    "op": op,
+                    "channel": "ticker",
+                    "market": symbol,
+                }),
+                WsChannel::Fills => json!({
+                    "op": op,
+                    "channel": "fills",
+                    "market": "",
+                }),
+                WsChannel::Orders => json!({
+                    "op": op,
+                    "channel": "orders",
+                    "market": "",
+                }),
+                WsChannel::OrderbookGrouped { symbol, grouping } => json!({
+                    "op": op,
+                    "channel": "orderbookGrouped",
+                    "market": symbol,
+                    "grouping": grouping.to_f64().unwrap_or_default(),
+                }),
+                // The stored value may have been built for the opposite
+                // direction (e.g. subscribed, then later unsubscribed) -
+                // override "op" rather than replaying it verbatim so
+                // unsubscribing a raw channel actually tells FTX to stop.
+                WsChannel::Raw(mut value) => {
+                    if let Some(object) = value.as_object_mut() {
+                        object.insert("op".to_string(), json!(op));
+                    }
+                    value
+                }
             };
 
             ws_connection
-                .send_message(Message::Text(
-                    json!({
-                        "op": op,
-                        "channel": channel,
-                        "market": symbol,
-                    })
-                    .to_string(),
-                ))
+                .send_message(Message::Text(message.to_string()))
                 .await;
         }
     }
 
+    fn ping_message() -> Message {
+        Message::Text(
+            json!({
+                "op": "ping",
+            })
+            .to_string(),
+        )
+    }
+
     pub fn start(ftx_ws_client: Arc<FtxWsClient>) {
-        if !ftx_ws_client
-            .is_started
-            .load(std::sync::atomic::Ordering::Relaxed)
-        {
-            let ping_message = Message::Text(
-                json!({
-                    "op": "ping",
-                })
-                .to_string(),
-            );
+        if ftx_ws_client.is_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        *ftx_ws_client.self_ref.write().unwrap() = Some(Arc::downgrade(&ftx_ws_client));
+        ftx_ws_client.keep_running.store(true, Ordering::SeqCst);
+        ftx_ws_client
+            .ws_client
+            .start(Self::ping_message(), ftx_ws_client.clone());
+    }
+
+    /// Stops the supervised reconnect loop. A connection that is currently
+    /// open keeps running until FTX drops it.
+    pub fn stop(&self) {
+        self.keep_running.store(false, Ordering::SeqCst);
+    }
+
+    /// Schedules a reconnect attempt after an exponential backoff, honouring
+    /// `reconnect_policy` and `keep_running`. Called from `on_disconnected`.
+    fn schedule_reconnect(&self) {
+        if !self.keep_running.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let attempt = self.reconnect_attempts.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(max_retries) = self.reconnect_policy.max_retries {
+            if attempt >= max_retries {
+                self.logger.write_fatal_error(
+                    "FtxWsClient".to_string(),
+                    format!(
+                        "Giving up reconnecting to FTX websocket after {} attempts",
+                        attempt
+                    ),
+                    None,
+                );
+                return;
+            }
+        }
+
+        let ftx_ws_client = match self.self_ref.read().unwrap().as_ref().and_then(Weak::upgrade) {
+            Some(ftx_ws_client) => ftx_ws_client,
+            None => return,
+        };
+
+        let backoff = self.reconnect_policy.backoff_for_attempt(attempt);
+
+        self.logger.write_warning(
+            "FtxWsClient".to_string(),
+            format!(
+                "Reconnecting to FTX websocket in {:?} (attempt {})",
+                backoff,
+                attempt + 1
+            ),
+            None,
+        );
+
+        tokio::spawn(async move {
+            tokio::time::sleep(backoff).await;
+
+            if !ftx_ws_client.keep_running.load(Ordering::Relaxed) {
+                return;
+            }
+
             ftx_ws_client
                 .ws_client
-                .start(ping_message, ftx_ws_client.clone());
-            ftx_ws_client
-                .is_started
-                .store(true, std::sync::atomic::Ordering::SeqCst);
-        }
+                .start(FtxWsClient::ping_message(), ftx_ws_client.clone());
+        });
     }
 }
 
 #[async_trait::async_trait]
 impl WsCallback for FtxWsClient {
     async fn on_connected(&self, ws_connection: Arc<WsConnection>) {
+        self.reconnect_attempts.store(0, Ordering::SeqCst);
+
         self.logger.write_info(
             "FtxWsClient".to_string(),
             "Connected to FTX websocket".to_string(),
             None,
         );
 
+        self.login(&ws_connection).await;
+
         self.subscribe_or_unsubscribe(ws_connection, self.channels.clone(), true)
-            .await; 
+            .await;
     }
 
-    async fn on_disconnected(&self, _: Arc<WsConnection>) {}
+    async fn on_disconnected(&self, _: Arc<WsConnection>) {
+        self.logger.write_warning(
+            "FtxWsClient".to_string(),
+            "Disconnected from FTX websocket".to_string(),
+            None,
+        );
+
+        self.schedule_reconnect();
+    }
 
     async fn on_data(&self, connection: Arc<WsConnection>, data: Message) {
         if let Message::Text(text) = data {
@@ -144,9 +318,56 @@ impl WsCallback for FtxWsClient {
                     connection.disconnect().await;
                 }
                 WsMessageType::Partial | WsMessageType::Update => {
-                    self.event_handler.on_data(WsDataEvent::new(response)).await
+                    match WsDataEvent::new(response) {
+                        Ok(event) => self.event_handler.on_data(event).await,
+                        Err(err) => self.logger.write_error(
+                            "FtxWsClient".to_string(),
+                            format!("Failed to build event: {}", err),
+                            None,
+                        ),
+                    }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(initial_secs: u64, max_secs: u64) -> ReconnectPolicy {
+        ReconnectPolicy {
+            initial_backoff: Duration::from_secs(initial_secs),
+            max_backoff: Duration::from_secs(max_secs),
+            max_retries: None,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_with_each_attempt() {
+        let policy = policy(1, 30);
+
+        assert_eq!(policy.backoff_for_attempt(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff_for_attempt(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff_for_attempt(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff_for_attempt(3), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max_backoff() {
+        let policy = policy(1, 30);
+
+        assert_eq!(policy.backoff_for_attempt(10), Duration::from_secs(30));
+        assert_eq!(policy.backoff_for_attempt(1000), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn backoff_does_not_overflow_on_huge_attempt_counts() {
+        let policy = policy(1, 30);
+
+        // `attempt.min(16)` bounds the exponent regardless of how high the
+        // real attempt counter has climbed, so this must not panic.
+        assert_eq!(policy.backoff_for_attempt(u32::MAX), Duration::from_secs(30));
+    }
+}
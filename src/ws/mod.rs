@@ -3,8 +3,12 @@ mod models;
 mod error;
 mod event_handler;
 mod ftx_ws_settings;
+mod order_book;
+mod rate_feed;
 
 pub use ftx_ws_client::*;
 pub use models::*;
 pub use error::*;
-pub use event_handler::*;
\ No newline at end of file
+pub use event_handler::*;
+pub use order_book::*;
+pub use rate_feed::*;
\ No newline at end of file
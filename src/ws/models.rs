@@ -2,6 +2,7 @@ pub use crate::common::{Coin, Id, MarketType, OrderInfo, Side, Symbol, TradeInfo
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use serde_with::{serde_as, TimestampSecondsWithFrac};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -12,16 +13,95 @@ pub enum WsChannel {
     Ticker(String),
     Fills,
     Orders,
+    /// The `orderbook` channel with a non-default price `grouping`, e.g. to
+    /// receive a book bucketed to $5 instead of FTX's default tick size.
+    OrderbookGrouped { symbol: String, grouping: Decimal },
+    /// Escape hatch for channels this enum doesn't model yet: the value is
+    /// forwarded to FTX as the subscription command, with its `op` field set
+    /// to `subscribe` or `unsubscribe`; all other fields are sent unchanged.
+    Raw(Value),
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+impl WsChannel {
+    /// Reconstructs the channel a message was received on from FTX's
+    /// `channel`/`market` fields, which is all an incoming message carries.
+    /// A channel name we don't recognise (e.g. one only reachable via
+    /// `WsChannel::Raw`) comes back as `WsChannel::Raw` rather than being
+    /// dropped, so its data can still reach an `EventHandler`.
+    pub(crate) fn from_wire(channel: &str, market: Option<&str>) -> WsChannel {
+        let market_owned = market.unwrap_or_default().to_string();
+
+        match channel {
+            // `grouping` isn't echoed back on data frames, so a grouped book
+            // is reported to the handler as a plain `Orderbook` channel.
+            "orderbook" | "orderbookGrouped" => WsChannel::Orderbook(market_owned),
+            "trades" => WsChannel::Trades(market_owned),
+            "ticker" => WsChannel::Ticker(market_owned),
+            "fills" => WsChannel::Fills,
+            "orders" => WsChannel::Orders,
+            other => WsChannel::Raw(json!({ "channel": other, "market": market })),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[serde(try_from = "RawWsResponse")]
 pub struct WsResponse {
     pub market: Option<String>,
+    pub channel: Option<String>,
     pub r#type: WsMessageType,
     pub data: Option<WsResponseData>,
 }
 
+/// Mirrors the wire shape of `WsResponse` with `data` left as raw JSON, so it
+/// can be decoded by its `channel` instead of relying on `#[serde(untagged)]`
+/// guesswork. `type` does not affect decoding; it is passed through as is.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawWsResponse {
+    market: Option<String>,
+    channel: Option<String>,
+    r#type: WsMessageType,
+    data: Option<Value>,
+}
+
+impl TryFrom<RawWsResponse> for WsResponse {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawWsResponse) -> Result<Self, Self::Error> {
+        let data = match raw.data {
+            None => None,
+            Some(value) => {
+                let channel = raw.channel.as_deref().ok_or_else(|| {
+                    serde::de::Error::custom("FTX message carries data but no channel")
+                })?;
+
+                Some(match channel {
+                    "ticker" => WsResponseData::Ticker(serde_json::from_value(value)?),
+                    "trades" => WsResponseData::Trades(serde_json::from_value(value)?),
+                    "orderbook" | "orderbookGrouped" => {
+                        WsResponseData::OrderbookData(serde_json::from_value(value)?)
+                    }
+                    "fills" => WsResponseData::Fill(serde_json::from_value(value)?),
+                    "orders" => WsResponseData::Order(serde_json::from_value(value)?),
+                    // A channel this enum doesn't model yet, e.g. one only
+                    // reachable via `WsChannel::Raw` - hand the raw payload
+                    // back instead of failing the whole frame.
+                    _ => WsResponseData::Raw(value),
+                })
+            }
+        };
+
+        Ok(Self {
+            market: raw.market,
+            channel: raw.channel,
+            r#type: raw.r#type,
+            data,
+        })
+    }
+}
+
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum WsMessageType {
@@ -34,17 +114,19 @@ pub enum WsMessageType {
     Info,
 }
 
-/// Represents the response received from FTX, and is used for
-/// deserialization
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// The typed payload of a `WsResponse`, selected explicitly from the
+/// message's `channel` rather than guessed via `#[serde(untagged)]`.
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(untagged)]
 pub enum WsResponseData {
     Ticker(TickerInfo),
     Trades(Vec<TradeInfo>),
     OrderbookData(OrderbookInfo),
     Fill(FillInfo),
     Order(OrderInfo),
+    /// Payload for a channel this enum doesn't model yet (see
+    /// `WsChannel::Raw`), handed back undecoded.
+    Raw(Value),
 }
 
 #[serde_as]
@@ -111,4 +193,72 @@ pub enum WsOrderbookAction {
 pub enum Liquidity {
     Maker,
     Taker,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_ticker_channel_into_typed_variant() {
+        let json = r#"{
+            "channel": "ticker",
+            "market": "BTC/USD",
+            "type": "update",
+            "data": {
+                "bid": "10000.5",
+                "ask": "10001.5",
+                "bidSize": "1",
+                "askSize": "2",
+                "last": "10000.5",
+                "time": 1621740952.5079553
+            }
+        }"#;
+
+        let response: WsResponse = serde_json::from_str(json).unwrap();
+
+        match response.data {
+            Some(WsResponseData::Ticker(ticker)) => {
+                assert_eq!(ticker.bid, Decimal::new(100005, 1));
+                assert_eq!(ticker.ask, Decimal::new(100015, 1));
+            }
+            other => panic!("expected Ticker, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unrecognized_channel_round_trips_to_raw() {
+        let json = r#"{
+            "channel": "newFeature",
+            "market": null,
+            "type": "update",
+            "data": {"foo": "bar"}
+        }"#;
+
+        let response: WsResponse = serde_json::from_str(json).unwrap();
+
+        match response.data {
+            Some(WsResponseData::Raw(value)) => assert_eq!(value["foo"], "bar"),
+            other => panic!("expected Raw, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_wire_falls_back_to_raw_for_unknown_channel() {
+        let channel = WsChannel::from_wire("newFeature", Some("BTC/USD"));
+        assert!(matches!(channel, WsChannel::Raw(_)));
+    }
+
+    #[test]
+    fn from_wire_recognises_the_built_in_channels() {
+        assert_eq!(
+            WsChannel::from_wire("orderbook", Some("BTC/USD")),
+            WsChannel::Orderbook("BTC/USD".to_string())
+        );
+        assert_eq!(
+            WsChannel::from_wire("orderbookGrouped", Some("BTC/USD")),
+            WsChannel::Orderbook("BTC/USD".to_string())
+        );
+        assert_eq!(WsChannel::from_wire("fills", None), WsChannel::Fills);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+
+use super::{OrderbookInfo, WsError, WsOrderbookAction};
+
+/// Local reconstruction of a single market's order book.
+///
+/// Consumes the `Partial` snapshot FTX sends on subscription and folds each
+/// subsequent `Update` delta into sorted bid/ask maps, dropping a price level
+/// once its size reaches zero. FTX's CRC32 checksum is validated after every
+/// applied message so a corrupted book can be detected and resnapshotted.
+#[derive(Debug, Default)]
+pub struct OrderBook {
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+    has_partial: bool,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a `Partial` or `Update` message to the book and validates its
+    /// checksum.
+    ///
+    /// Returns `WsError::MissingPartial` if an `Update` arrives before the
+    /// initial `Partial`, or `WsError::IncorrectChecksum` if the resulting
+    /// book does not hash to the `checksum` FTX sent alongside it.
+    pub fn apply(&mut self, data: &OrderbookInfo) -> Result<(), WsError> {
+        match data.action {
+            WsOrderbookAction::Partial => {
+                self.bids.clear();
+                self.asks.clear();
+                self.has_partial = true;
+            }
+            WsOrderbookAction::Update => {
+                if !self.has_partial {
+                    return Err(WsError::MissingPartial);
+                }
+            }
+        }
+
+        for &(price, size) in &data.bids {
+            Self::apply_level(&mut self.bids, price, size);
+        }
+
+        for &(price, size) in &data.asks {
+            Self::apply_level(&mut self.asks, price, size);
+        }
+
+        if self.checksum() != data.checksum {
+            return Err(WsError::IncorrectChecksum);
+        }
+
+        Ok(())
+    }
+
+    fn apply_level(levels: &mut BTreeMap<Decimal, Decimal>, price: Decimal, size: Decimal) {
+        if size.is_zero() {
+            levels.remove(&price);
+        } else {
+            levels.insert(price, size);
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
+        self.bids.iter().next_back().map(|(&price, &size)| (price, size))
+    }
+
+    pub fn best_ask(&self) -> Option<(Decimal, Decimal)> {
+        self.asks.iter().next().map(|(&price, &size)| (price, size))
+    }
+
+    /// Returns up to `n` levels per side, bids descending and asks ascending,
+    /// matching the order FTX streams them in.
+    pub fn get_depth(&self, n: usize) -> (Vec<(Decimal, Decimal)>, Vec<(Decimal, Decimal)>) {
+        let bids = self.bids.iter().rev().take(n).map(|(&price, &size)| (price, size)).collect();
+        let asks = self.asks.iter().take(n).map(|(&price, &size)| (price, size)).collect();
+
+        (bids, asks)
+    }
+
+    /// Builds FTX's checksum string over the top 100 levels (bid and ask
+    /// interleaved at each index) and CRC32-hashes it.
+    fn checksum(&self) -> u32 {
+        let mut bids = self.bids.iter().rev();
+        let mut asks = self.asks.iter();
+        let mut tokens = Vec::new();
+
+        for _ in 0..100 {
+            let bid = bids.next();
+            let ask = asks.next();
+
+            if bid.is_none() && ask.is_none() {
+                break;
+            }
+
+            if let Some((price, size)) = bid {
+                tokens.push(format!("{}:{}", price, size));
+            }
+
+            if let Some((price, size)) = ask {
+                tokens.push(format!("{}:{}", price, size));
+            }
+        }
+
+        crc32fast::hash(tokens.join(":").as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn level(price: i64, size: i64) -> (Decimal, Decimal) {
+        (Decimal::from(price), Decimal::from(size))
+    }
+
+    fn orderbook_info(
+        action: WsOrderbookAction,
+        bids: Vec<(Decimal, Decimal)>,
+        asks: Vec<(Decimal, Decimal)>,
+        checksum: u32,
+    ) -> OrderbookInfo {
+        OrderbookInfo {
+            action,
+            bids,
+            asks,
+            checksum,
+            time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn applies_partial_then_update_and_matches_checksum() {
+        let mut book = OrderBook::new();
+
+        let partial = orderbook_info(
+            WsOrderbookAction::Partial,
+            vec![level(5000, 8), level(4999, 3)],
+            vec![level(5001, 3), level(5002, 6)],
+            2776191223,
+        );
+        book.apply(&partial).unwrap();
+
+        assert_eq!(book.best_bid(), Some(level(5000, 8)));
+        assert_eq!(book.best_ask(), Some(level(5001, 3)));
+
+        // Removes 4999 (size -> 0), resizes 5001, adds a new bid level below the best bid.
+        let update = orderbook_info(
+            WsOrderbookAction::Update,
+            vec![level(4999, 0), level(4998, 5)],
+            vec![level(5001, 4)],
+            399850836,
+        );
+        book.apply(&update).unwrap();
+
+        assert_eq!(book.best_bid(), Some(level(5000, 8)));
+        assert_eq!(
+            book.get_depth(10),
+            (
+                vec![level(5000, 8), level(4998, 5)],
+                vec![level(5001, 4), level(5002, 6)]
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_update_before_partial() {
+        let mut book = OrderBook::new();
+
+        let update = orderbook_info(WsOrderbookAction::Update, vec![level(5000, 8)], vec![], 0);
+
+        assert!(matches!(book.apply(&update), Err(WsError::MissingPartial)));
+    }
+
+    #[test]
+    fn rejects_mismatched_checksum() {
+        let mut book = OrderBook::new();
+
+        let partial = orderbook_info(
+            WsOrderbookAction::Partial,
+            vec![level(5000, 8)],
+            vec![level(5001, 3)],
+            0,
+        );
+
+        assert!(matches!(book.apply(&partial), Err(WsError::IncorrectChecksum)));
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use rust_decimal::Decimal;
+use rust_extensions::Logger;
+
+use super::{
+    EventHandler, FtxWsClient, ReconnectPolicy, Symbol, TickerInfo, WsChannel, WsDataEvent,
+    WsResponseData,
+};
+
+/// A cached top-of-book price snapshot for a single market.
+#[derive(Copy, Clone, Debug)]
+pub struct Rate {
+    pub bid: Decimal,
+    pub ask: Decimal,
+    pub mid: Decimal,
+}
+
+impl From<TickerInfo> for Rate {
+    fn from(ticker: TickerInfo) -> Self {
+        Self {
+            bid: ticker.bid,
+            ask: ticker.ask,
+            mid: (ticker.bid + ticker.ask) / Decimal::TWO,
+        }
+    }
+}
+
+/// Synchronous access to the latest known rate for a market, for code that
+/// wants a price snapshot without wiring up its own async `EventHandler`.
+pub trait LatestRate {
+    fn latest_rate(&self, market: &Symbol) -> Option<Rate>;
+}
+
+/// Subscribes to the FTX ticker channel for a fixed set of markets and keeps
+/// an atomically-updated cache of the most recent `Rate` per symbol.
+pub struct FtxRateFeed {
+    rates: Arc<RwLock<HashMap<Symbol, Rate>>>,
+}
+
+impl FtxRateFeed {
+    pub fn new(
+        markets: Vec<Symbol>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> Arc<Self> {
+        let rates = Arc::new(RwLock::new(HashMap::new()));
+        let channels = markets.into_iter().map(WsChannel::Ticker).collect();
+        let handler = Arc::new(RateFeedHandler {
+            rates: rates.clone(),
+        });
+
+        FtxWsClient::start(Arc::new(FtxWsClient::new(
+            handler,
+            logger,
+            channels,
+            None,
+            ReconnectPolicy::default(),
+        )));
+
+        Arc::new(Self { rates })
+    }
+}
+
+impl LatestRate for FtxRateFeed {
+    fn latest_rate(&self, market: &Symbol) -> Option<Rate> {
+        self.rates.read().unwrap().get(market).copied()
+    }
+}
+
+struct RateFeedHandler {
+    rates: Arc<RwLock<HashMap<Symbol, Rate>>>,
+}
+
+#[async_trait::async_trait]
+impl EventHandler for RateFeedHandler {
+    async fn on_data(&self, event: WsDataEvent) {
+        let market = match event.market {
+            Some(market) => market,
+            None => return,
+        };
+
+        if let WsResponseData::Ticker(ticker) = event.data {
+            self.rates.write().unwrap().insert(market, ticker.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::WsMessageType;
+    use chrono::Utc;
+
+    fn ticker(bid: i64, ask: i64) -> TickerInfo {
+        TickerInfo {
+            bid: Decimal::from(bid),
+            ask: Decimal::from(ask),
+            bid_size: Decimal::ONE,
+            ask_size: Decimal::ONE,
+            last: Decimal::from(bid),
+            time: Utc::now(),
+        }
+    }
+
+    fn ticker_event(market: &str, ticker: TickerInfo) -> WsDataEvent {
+        WsDataEvent {
+            data: WsResponseData::Ticker(ticker),
+            market: Some(market.to_string()),
+            channel: WsChannel::Ticker(market.to_string()),
+            message_type: WsMessageType::Update,
+        }
+    }
+
+    #[test]
+    fn rate_mid_is_the_average_of_bid_and_ask() {
+        let rate: Rate = ticker(100, 102).into();
+
+        assert_eq!(rate.bid, Decimal::from(100));
+        assert_eq!(rate.ask, Decimal::from(102));
+        assert_eq!(rate.mid, Decimal::from(101));
+    }
+
+    #[tokio::test]
+    async fn on_data_updates_the_shared_rate_for_its_market() {
+        let rates = Arc::new(RwLock::new(HashMap::new()));
+        let handler = RateFeedHandler {
+            rates: rates.clone(),
+        };
+
+        handler
+            .on_data(ticker_event("BTC/USD", ticker(100, 102)))
+            .await;
+
+        let rate = rates.read().unwrap().get("BTC/USD").copied().unwrap();
+        assert_eq!(rate.mid, Decimal::from(101));
+    }
+
+    #[tokio::test]
+    async fn on_data_ignores_events_with_no_market() {
+        let rates = Arc::new(RwLock::new(HashMap::new()));
+        let handler = RateFeedHandler {
+            rates: rates.clone(),
+        };
+
+        handler
+            .on_data(WsDataEvent {
+                data: WsResponseData::Ticker(ticker(100, 102)),
+                market: None,
+                channel: WsChannel::Ticker("BTC/USD".to_string()),
+                message_type: WsMessageType::Update,
+            })
+            .await;
+
+        assert!(rates.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn latest_rate_is_none_for_an_unknown_market() {
+        let rates: Arc<RwLock<HashMap<Symbol, Rate>>> = Arc::new(RwLock::new(HashMap::new()));
+        let feed = FtxRateFeed { rates };
+
+        assert!(feed.latest_rate(&"ETH/USD".to_string()).is_none());
+    }
+}